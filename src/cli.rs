@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use clap::Parser;
+
+/// Which field of a `Sum` to print. Selected with `--metrics`, e.g.
+/// `--metrics diapers,sleep`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    TotalDiapers,
+    PooDiapers,
+    Bottle,
+    BottleSessions,
+    BreastFeeding,
+    Pumping,
+    TummyTime,
+    MaxSleep,
+    TotalSleep,
+}
+
+impl Metric {
+    /// Every metric, in the order they're printed by default.
+    pub const ALL: [Metric; 9] = [
+        Metric::TotalDiapers,
+        Metric::PooDiapers,
+        Metric::Bottle,
+        Metric::BottleSessions,
+        Metric::BreastFeeding,
+        Metric::Pumping,
+        Metric::TummyTime,
+        Metric::MaxSleep,
+        Metric::TotalSleep,
+    ];
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "diapers" => Ok(Metric::TotalDiapers),
+            "poo" => Ok(Metric::PooDiapers),
+            "bottle" => Ok(Metric::Bottle),
+            "bottle-sessions" => Ok(Metric::BottleSessions),
+            "breast" => Ok(Metric::BreastFeeding),
+            "pumping" => Ok(Metric::Pumping),
+            "tummy-time" => Ok(Metric::TummyTime),
+            "max-sleep" => Ok(Metric::MaxSleep),
+            "sleep" => Ok(Metric::TotalSleep),
+            other => Err(format!("unknown metric '{}'", other)),
+        }
+    }
+}
+
+/// Output format for the rolling-window summaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Human-readable, one report per window (the default).
+    Text,
+    /// One JSON object per window.
+    Json,
+    /// A header row followed by one row per window.
+    Csv,
+}
+
+/// Rolling-window summaries of baby-tracking data.
+#[derive(Debug, Parser)]
+#[command(name = "babysum", author, version, about)]
+pub struct Args {
+    /// Number of days to average over for each rolling window.
+    #[arg(long, default_value_t = 7)]
+    pub window: usize,
+
+    /// Output format: text, json, or csv.
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: Format,
+
+    /// Only include days on or after this date (YYYY-MM-DD).
+    #[arg(long)]
+    pub since: Option<NaiveDate>,
+
+    /// Only include days on or before this date (YYYY-MM-DD).
+    #[arg(long)]
+    pub until: Option<NaiveDate>,
+
+    /// Read events from FILE instead of stdin.
+    #[arg(long)]
+    pub input: Option<PathBuf>,
+
+    /// Comma-separated list of metrics to print (default: all). Applies to
+    /// text output only; json and csv output always include every metric.
+    #[arg(long, value_delimiter = ',')]
+    pub metrics: Vec<Metric>,
+
+    /// Also print an hour-of-day distribution chart for feedings, sleep,
+    /// and diapers.
+    #[arg(long)]
+    pub hours: bool,
+
+    /// Also print the peak sleep hour and the longest single sleep
+    /// stretch, across the whole input.
+    #[arg(long)]
+    pub sleep_stats: bool,
+}
+
+impl Args {
+    /// The metrics to print, defaulting to every metric when none were
+    /// selected on the command line.
+    pub fn selected_metrics(&self) -> &[Metric] {
+        if self.metrics.is_empty() {
+            &Metric::ALL
+        } else {
+            &self.metrics
+        }
+    }
+}
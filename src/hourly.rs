@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+
+use time_util::for_each_hour_slice;
+
+/// Width, in characters, of the longest bar in a rendered chart.
+const CHART_WIDTH: usize = 40;
+
+/// Counts accumulated for a single clock hour across the whole input.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HourBucket {
+    pub feedings: u32,
+    pub sleep_seconds: u64,
+    pub diapers: u32,
+}
+
+/// Buckets feedings, sleep, and diapers by the 24 hours of the day, and
+/// renders each as an ASCII bar chart.
+///
+/// Only the span of hours actually seen in the input is rendered, growing lazily
+/// from the earliest to the latest used hour (gaps in between are filled
+/// with empty buckets so the chart isn't misleading about scale).
+#[derive(Debug, Default)]
+pub struct HourlyChart {
+    buckets: BTreeMap<u32, HourBucket>,
+}
+
+impl HourlyChart {
+    pub fn new() -> Self {
+        HourlyChart { buckets: BTreeMap::new() }
+    }
+
+    pub fn record_feeding(&mut self, hour: u32) {
+        self.buckets.entry(hour).or_default().feedings += 1;
+    }
+
+    /// Record a completed sleep event, splitting its `[start, end)`
+    /// interval across the clock hours it spans rather than attributing
+    /// the whole thing to a single hour.
+    pub fn record_sleep(&mut self, start: DateTime<Local>, end: DateTime<Local>) {
+        for_each_hour_slice(start, end, |hour, seconds| {
+            self.buckets.entry(hour).or_default().sleep_seconds += seconds;
+        });
+    }
+
+    pub fn record_diaper(&mut self, hour: u32) {
+        self.buckets.entry(hour).or_default().diapers += 1;
+    }
+
+    /// All hours between the earliest and latest seen, inclusive, with
+    /// empty buckets filled in for any gaps.
+    fn hour_range(&self) -> Vec<(u32, HourBucket)> {
+        let (first, last) = match (self.buckets.keys().next(), self.buckets.keys().next_back()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return Vec::new(),
+        };
+        (first..=last)
+            .map(|hour| (hour, self.buckets.get(&hour).cloned().unwrap_or_default()))
+            .collect()
+    }
+
+    /// Render the three hour-of-day charts (feedings, sleep, diapers) as
+    /// human-readable text.
+    pub fn render(&self) -> String {
+        let rows = self.hour_range();
+        if rows.is_empty() {
+            return String::new();
+        }
+        let mut out = String::new();
+        out.push_str("Feedings by Hour:\n");
+        out.push_str(&render_bars(&rows, |b| b.feedings as u64));
+        out.push_str("\nSleep by Hour:\n");
+        out.push_str(&render_bars(&rows, |b| b.sleep_seconds));
+        out.push_str("\nDiapers by Hour:\n");
+        out.push_str(&render_bars(&rows, |b| b.diapers as u64));
+        out
+    }
+}
+
+fn render_bars(rows: &[(u32, HourBucket)], value_of: impl Fn(&HourBucket) -> u64) -> String {
+    let max = rows.iter().map(|(_, b)| value_of(b)).max().unwrap_or(0);
+    let mut out = String::new();
+    for (hour, bucket) in rows {
+        let value = value_of(bucket);
+        let bar_len = if max == 0 { 0 } else { (value as usize * CHART_WIDTH) / max as usize };
+        out.push_str(&format!("{:02}:00 | {:width$} {}\n", hour, "#".repeat(bar_len), value, width = CHART_WIDTH));
+    }
+    out
+}
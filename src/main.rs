@@ -1,22 +1,63 @@
 extern crate babystats;
 extern crate chrono;
+extern crate clap;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+mod cli;
+mod hourly;
+mod sleep_report;
+mod time_util;
 
 use std::error::Error;
 use std::collections::BTreeMap;
+use std::fs::File;
 use std::io;
 use std::process;
 use babystats::BabyManagerData;
+use chrono::Timelike;
+use clap::Parser;
+use cli::{Args, Format, Metric};
+use hourly::HourlyChart;
+use sleep_report::SleepReport;
+
+/// Serializes a `chrono::Duration` as its total number of seconds.
+mod duration_secs {
+    use chrono;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(duration: &chrono::Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(duration.num_seconds())
+    }
 
-#[derive(Debug)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<chrono::Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let secs = i64::deserialize(deserializer)?;
+        Ok(chrono::Duration::seconds(secs))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 struct Sum {
     total_diapers: i32,
     poo_diapers: i32,
     bottle_oz: f32,
     bottle_sessions: i32,
+    #[serde(with = "duration_secs")]
     breast_duration: chrono::Duration,
     pumping_oz: f32,
+    #[serde(with = "duration_secs")]
     tummy_time_duration: chrono::Duration,
+    #[serde(with = "duration_secs")]
     max_sleep_duration: chrono::Duration,
+    #[serde(with = "duration_secs")]
     total_sleep_duration: chrono::Duration,
 }
 
@@ -34,9 +75,76 @@ impl Sum {
             total_sleep_duration: chrono::Duration::seconds(0),
         }
     }
+
+    fn label(metric: Metric) -> &'static str {
+        match metric {
+            Metric::TotalDiapers => "Total Diapers",
+            Metric::PooDiapers => "Poo Diapers",
+            Metric::Bottle => "Bottle",
+            Metric::BottleSessions => "Bottle Sessions",
+            Metric::BreastFeeding => "Breast Feeding",
+            Metric::Pumping => "Pumping",
+            Metric::TummyTime => "Tummy Time",
+            Metric::MaxSleep => "Max Sleep",
+            Metric::TotalSleep => "Total Sleep",
+        }
+    }
+
+    /// The sum of every duration-valued metric, used as the denominator
+    /// when showing a duration's share of the window as a percentage.
+    /// `max_sleep_duration` is deliberately excluded: it's the longest
+    /// single nap *within* `total_sleep_duration`, not additional time,
+    /// so including it would double-count sleep.
+    fn duration_total(&self) -> i64 {
+        (self.breast_duration + self.tummy_time_duration + self.total_sleep_duration).num_seconds()
+    }
+
+    /// `duration`'s percentage of `duration_total()`, rounded to the
+    /// nearest whole percent. Zero when there's no duration to compare
+    /// against, rather than dividing by zero.
+    fn duration_percent(&self, duration: chrono::Duration) -> i64 {
+        let total = self.duration_total();
+        if total == 0 {
+            0
+        } else {
+            (duration.num_seconds() * 100 + total / 2) / total
+        }
+    }
+
+    fn value(&self, metric: Metric) -> String {
+        match metric {
+            Metric::TotalDiapers => format!("{}", self.total_diapers),
+            Metric::PooDiapers => format!("{}", self.poo_diapers),
+            Metric::Bottle => format!("{:.1} oz ({:.1} oz per session)", self.bottle_oz, self.bottle_oz / self.bottle_sessions as f32),
+            Metric::BottleSessions => format!("{}", self.bottle_sessions),
+            Metric::BreastFeeding => format!("{} ({}%)", FormattedDuration(self.breast_duration), self.duration_percent(self.breast_duration)),
+            Metric::Pumping => format!("{:.1} oz", self.pumping_oz),
+            Metric::TummyTime => format!("{} ({}%)", FormattedDuration(self.tummy_time_duration), self.duration_percent(self.tummy_time_duration)),
+            Metric::MaxSleep => format!("{} ({}%)", FormattedDuration(self.max_sleep_duration), self.duration_percent(self.max_sleep_duration)),
+            Metric::TotalSleep => format!("{} ({}%)", FormattedDuration(self.total_sleep_duration), self.duration_percent(self.total_sleep_duration)),
+        }
+    }
+
+    /// Render the given metrics as an aligned table: labels left-aligned and
+    /// padded to a common column, values right-aligned, with divider lines above and
+    /// below.
+    fn format_selected(&self, metrics: &[Metric]) -> String {
+        let rows: Vec<(&'static str, String)> = metrics.iter().map(|&m| (Sum::label(m), self.value(m))).collect();
+        let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let value_width = rows.iter().map(|(_, value)| value.len()).max().unwrap_or(0);
+        let divider = "-".repeat(label_width + value_width + 2);
+        let mut out = String::new();
+        out.push_str(&divider);
+        out.push('\n');
+        for (label, value) in &rows {
+            out.push_str(&format!("{:<label_width$}: {:>value_width$}\n", label, value, label_width = label_width, value_width = value_width));
+        }
+        out.push_str(&divider);
+        out
+    }
 }
 
-struct FormattedDuration(chrono::Duration);
+pub(crate) struct FormattedDuration(pub(crate) chrono::Duration);
 
 impl std::fmt::Display for FormattedDuration {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -58,25 +166,68 @@ impl std::fmt::Display for FormattedDuration {
 
 impl std::fmt::Display for Sum {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Total Diapers: {}\n", self.total_diapers)?;
-        write!(f, "Poo Diapers: {}\n", self.poo_diapers)?;
-        write!(f, "Bottle: {:.1} oz ({:.1} oz per session)\n", self.bottle_oz, self.bottle_oz / self.bottle_sessions as f32)?;
-        write!(f, "Bottle Sessions: {}\n", self.bottle_sessions)?;
-        write!(f, "Breast Feeding: {}\n", FormattedDuration(self.breast_duration))?;
-        write!(f, "Pumping: {:.1} oz\n", self.pumping_oz)?;
-        write!(f, "Tummy Time: {}\n", FormattedDuration(self.tummy_time_duration))?;
-        write!(f, "Max Sleep: {}\n", FormattedDuration(self.max_sleep_duration))?;
-        write!(f, "Total Sleep: {}\n", FormattedDuration(self.total_sleep_duration))?;
-        Ok(())
+        write!(f, "{}", self.format_selected(&cli::Metric::ALL))
     }
 }
 
+/// Serializes a `chrono::NaiveDate` as its `YYYY-MM-DD` string form.
+/// `NaiveDate`'s own `Serialize` impl is gated behind chrono's `serde`
+/// cargo feature, which isn't guaranteed to be enabled, so spell out the
+/// conversion explicitly rather than relying on it.
+mod date_ymd {
+    use chrono::NaiveDate;
+    use serde::Serializer;
+
+    pub fn serialize<S>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&date.format("%Y-%m-%d").to_string())
+    }
+}
+
+/// A single window's mean `Sum`, tagged with the window's ending date.
+/// This is what gets emitted for `--format json`.
+#[derive(Serialize)]
+struct WindowRecord<'a> {
+    #[serde(with = "date_ymd")]
+    date: chrono::NaiveDate,
+    #[serde(flatten)]
+    sum: &'a Sum,
+}
+
+const CSV_HEADER: &str = "date,total_diapers,poo_diapers,bottle_oz,bottle_sessions,breast_duration_secs,pumping_oz,tummy_time_duration_secs,max_sleep_duration_secs,total_sleep_duration_secs";
+
+fn csv_row(date: chrono::NaiveDate, sum: &Sum) -> String {
+    format!(
+        "{},{},{},{},{},{},{},{},{},{}",
+        date,
+        sum.total_diapers,
+        sum.poo_diapers,
+        sum.bottle_oz,
+        sum.bottle_sessions,
+        sum.breast_duration.num_seconds(),
+        sum.pumping_oz,
+        sum.tummy_time_duration.num_seconds(),
+        sum.max_sleep_duration.num_seconds(),
+        sum.total_sleep_duration.num_seconds(),
+    )
+}
+
 fn run() -> Result<(), Box<Error>> {
-    let mut rdr = BabyManagerData::from_reader(io::stdin());
+    let args = Args::parse();
+
+    let reader: Box<io::Read> = match &args.input {
+        Some(path) => Box::new(File::open(path)?),
+        None => Box::new(io::stdin()),
+    };
+    let mut rdr = BabyManagerData::from_reader(reader);
     let mut events: Vec<_> = rdr.into_iter().map(|r| r.unwrap()).collect();
     events.sort_by_key(|e| e.time());
     let mut m: BTreeMap<_, _> = BTreeMap::new();
     let mut prev_bottle: Option<chrono::DateTime<chrono::Local>> = None;
+    let mut hourly = HourlyChart::new();
+    let mut sleep_report = SleepReport::new();
     for event in events {
         match event {
             babystats::Event::Diaper(ref ev) => {
@@ -85,9 +236,11 @@ fn run() -> Result<(), Box<Error>> {
                 if ev.poo {
                     s.poo_diapers += 1;
                 }
+                hourly.record_diaper(ev.time.time().hour());
             },
             babystats::Event::Feeding(ref ev) => {
                 let s = m.entry(ev.time().date()).or_insert(Sum::new());
+                hourly.record_feeding(ev.time().time().hour());
                 match *ev {
                     babystats::FeedingEvent::Bottle(ref bev) => {
                         s.bottle_sessions += 1;
@@ -119,14 +272,37 @@ fn run() -> Result<(), Box<Error>> {
                         s.max_sleep_duration = ev.duration;
                     }
                     s.total_sleep_duration = s.total_sleep_duration + ev.duration;
+                    hourly.record_sleep(ev.start, end);
+                    sleep_report.record(ev.start, end, ev.duration);
                 }
             },
             _ => {},
         };
     }
+
+    if let Some(since) = args.since {
+        m.retain(|date, _| date.naive_local() >= since);
+    }
+    if let Some(until) = args.until {
+        m.retain(|date, _| date.naive_local() <= until);
+    }
+    if m.is_empty() {
+        return Err(From::from("no data in the requested date range"));
+    }
+
+    let window_days = args.window;
+    if window_days == 0 {
+        return Err(From::from("--window must be at least 1"));
+    }
     let summaries: Vec<_> = m.iter().map(|x| x).collect();
-    const WINDOW_DAYS: usize = 7;
-    for window in summaries.windows(WINDOW_DAYS) {
+    if summaries.len() < window_days {
+        return Err(From::from(format!("only {} day(s) of data, but --window {} was requested", summaries.len(), window_days)));
+    }
+    let metrics = args.selected_metrics();
+    if args.format == Format::Csv {
+        println!("{}", CSV_HEADER);
+    }
+    for window in summaries.windows(window_days) {
         let sum = window.iter().fold(Sum::new(), |mut acc, &(_, x)| {
             acc.total_diapers += x.total_diapers;
             acc.poo_diapers += x.poo_diapers;
@@ -140,23 +316,32 @@ fn run() -> Result<(), Box<Error>> {
             acc
         });
         let mean_sum = Sum{
-            total_diapers: sum.total_diapers / WINDOW_DAYS as i32,
-            poo_diapers: sum.poo_diapers / WINDOW_DAYS as i32,
-            bottle_oz: sum.bottle_oz / WINDOW_DAYS as f32,
-            bottle_sessions: sum.bottle_sessions / WINDOW_DAYS as i32,
-            breast_duration: chrono::Duration::seconds(sum.breast_duration.num_seconds() / WINDOW_DAYS as i64),
-            pumping_oz: sum.pumping_oz / WINDOW_DAYS as f32,
-            tummy_time_duration: chrono::Duration::seconds(sum.tummy_time_duration.num_seconds() / WINDOW_DAYS as i64),
-            max_sleep_duration: chrono::Duration::seconds(sum.max_sleep_duration.num_seconds() / WINDOW_DAYS as i64),
-            total_sleep_duration: chrono::Duration::seconds(sum.total_sleep_duration.num_seconds() / WINDOW_DAYS as i64),
+            total_diapers: sum.total_diapers / window_days as i32,
+            poo_diapers: sum.poo_diapers / window_days as i32,
+            bottle_oz: sum.bottle_oz / window_days as f32,
+            bottle_sessions: sum.bottle_sessions / window_days as i32,
+            breast_duration: chrono::Duration::seconds(sum.breast_duration.num_seconds() / window_days as i64),
+            pumping_oz: sum.pumping_oz / window_days as f32,
+            tummy_time_duration: chrono::Duration::seconds(sum.tummy_time_duration.num_seconds() / window_days as i64),
+            max_sleep_duration: chrono::Duration::seconds(sum.max_sleep_duration.num_seconds() / window_days as i64),
+            total_sleep_duration: chrono::Duration::seconds(sum.total_sleep_duration.num_seconds() / window_days as i64),
         };
         if let Some(&(date, _)) = window.last() {
-            println!("{:?}:\n{}", date, mean_sum);
+            let date = date.naive_local();
+            match args.format {
+                Format::Text => println!("{}:\n{}", date, mean_sum.format_selected(metrics)),
+                Format::Json => println!("{}", serde_json::to_string(&WindowRecord { date, sum: &mean_sum })?),
+                Format::Csv => println!("{}", csv_row(date, &mean_sum)),
+            }
         }
     }
-    //for (date, summary) in summaries {
-    //    println!("{:?}: {:?}", date, summary);
-    //}
+
+    if args.hours {
+        print!("{}", hourly.render());
+    }
+    if args.sleep_stats {
+        print!("{}", sleep_report.render());
+    }
     Ok(())
 }
 
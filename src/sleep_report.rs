@@ -0,0 +1,65 @@
+use chrono::{DateTime, Duration, Local};
+
+use time_util::for_each_hour_slice;
+
+/// The single longest uninterrupted `Sleep` event seen so far.
+#[derive(Debug, Clone, Copy)]
+struct LongestSleep {
+    start: DateTime<Local>,
+    duration: Duration,
+}
+
+/// Dataset-wide sleep analytics: which clock hour the baby is most often
+/// asleep during, and the longest single stretch of uninterrupted sleep.
+#[derive(Debug, Default)]
+pub struct SleepReport {
+    /// Total seconds of sleep coverage accumulated for each of the 24
+    /// clock hours, across every night in the input.
+    hour_coverage: [u64; 24],
+    longest: Option<LongestSleep>,
+}
+
+impl SleepReport {
+    pub fn new() -> Self {
+        SleepReport { hour_coverage: [0; 24], longest: None }
+    }
+
+    /// Record a completed sleep event, splitting its `[start, end]`
+    /// interval across the clock hours it spans and tracking it as the
+    /// new longest stretch if it is one.
+    pub fn record(&mut self, start: DateTime<Local>, end: DateTime<Local>, duration: Duration) {
+        for_each_hour_slice(start, end, |hour, secs| {
+            self.hour_coverage[hour as usize] += secs;
+        });
+
+        if self.longest.is_none_or(|l| duration > l.duration) {
+            self.longest = Some(LongestSleep { start, duration });
+        }
+    }
+
+    /// The clock hour with the most accumulated sleep coverage, and that
+    /// total, in seconds. `None` if no sleep events were recorded.
+    fn peak_hour(&self) -> Option<(usize, u64)> {
+        self.hour_coverage
+            .iter()
+            .cloned()
+            .enumerate()
+            .max_by_key(|&(_, secs)| secs)
+            .filter(|&(_, secs)| secs > 0)
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        if let Some((hour, secs)) = self.peak_hour() {
+            out.push_str(&format!("Peak Sleep Hour: {:02}:00 ({} total)\n", hour, super::FormattedDuration(Duration::seconds(secs as i64))));
+        }
+        if let Some(longest) = self.longest {
+            out.push_str(&format!(
+                "Longest Sleep Stretch: {} starting {}\n",
+                super::FormattedDuration(longest.duration),
+                longest.start.format("%Y-%m-%d %H:%M"),
+            ));
+        }
+        out
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Duration, Local, Timelike};
+
+/// Calls `f(hour_of_day, seconds)` once per clock-hour slice of the
+/// half-open interval `[start, end)`, splitting across hour (and day)
+/// boundaries as needed. Used to bucket an event that spans multiple
+/// hours (e.g. an overnight sleep) by the hours it actually covers,
+/// rather than attributing it entirely to a single hour.
+pub fn for_each_hour_slice(start: DateTime<Local>, end: DateTime<Local>, mut f: impl FnMut(u32, u64)) {
+    let mut cur = start;
+    while cur < end {
+        let hour = cur.time().hour();
+        let boundary = next_hour_boundary(cur);
+        let slice_end = if boundary < end { boundary } else { end };
+        let secs = (slice_end - cur).num_seconds().max(0) as u64;
+        f(hour, secs);
+        cur = slice_end;
+    }
+}
+
+/// The next clock-hour boundary strictly after `dt` (e.g. 14:37:12 -> 15:00:00,
+/// and 14:00:00 -> 15:00:00).
+fn next_hour_boundary(dt: DateTime<Local>) -> DateTime<Local> {
+    let start_of_hour = dt - Duration::minutes(dt.minute() as i64) - Duration::seconds(dt.second() as i64) - Duration::nanoseconds(dt.nanosecond() as i64);
+    start_of_hour + Duration::hours(1)
+}